@@ -0,0 +1,102 @@
+// Ogg metadata reader written in Rust
+//
+// Copyright (c) 2016 est31 <MTest31@outlook.com>
+// and contributors. All rights reserved.
+// Licensed under MIT license, or Apache 2 license,
+// at your option. Please see the LICENSE file
+// attached to this source distribution for details.
+
+use std::io::{Cursor, Read};
+use byteorder::{ReadBytesExt, BigEndian};
+use std::time::Duration;
+use std::fmt;
+use crate::OggMetadataError;
+use crate::AudioMetadata;
+
+/**
+Metadata for FLAC encapsulated inside an Ogg container.
+*/
+pub struct Metadata {
+	pub channels :u8,
+	pub sample_rate :u32,
+	pub bits_per_sample :u8,
+	pub length_in_samples :Option<u64>,
+}
+
+impl AudioMetadata for Metadata {
+	fn get_output_channel_count(&self) -> u8 {
+		self.channels
+	}
+	fn get_duration(&self) -> Option<Duration> {
+		self.length_in_samples.map(|l|
+			Duration::from_millis(
+				((l as f64) / (self.sample_rate as f64) * 1000.0)
+			as u64)
+		)
+	}
+}
+
+impl fmt::Debug for Metadata {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.length_in_samples {
+			Some(l) => {
+				let duration_raw_secs = (l as f64) / (self.sample_rate as f64);
+				write!(f, "{} channels, with {} Hz sample rate, {} bits per sample and duration of {}",
+					self.channels, self.sample_rate, self.bits_per_sample,
+					crate::format_duration(duration_raw_secs))
+			},
+			None => write!(f, "{} channels, with {} Hz sample rate and {} bits per sample",
+				self.channels, self.sample_rate, self.bits_per_sample),
+		}
+	}
+}
+
+pub struct IdentHeader {
+	pub channels :u8,
+	pub sample_rate :u32,
+	pub bits_per_sample :u8,
+	pub total_samples :u64,
+}
+
+#[allow(unused_variables)]
+pub fn read_header_ident(packet :&[u8]) -> Result<IdentHeader, OggMetadataError> {
+	let mut rdr = Cursor::new(packet);
+
+	// Major and minor version of the FLAC-in-Ogg mapping.
+	let version = rdr.read_u16::<BigEndian>()?;
+	// Number of header packets following this one, not counting it.
+	let num_header_packets = rdr.read_u16::<BigEndian>()?;
+
+	// Literal "fLaC" marker preceding the native FLAC metadata blocks.
+	let mut flac_marker = [0; 4];
+	rdr.read_exact(&mut flac_marker)?;
+	if &flac_marker != b"fLaC" {
+		return Err(OggMetadataError::UnrecognizedFormat);
+	}
+
+	// Native FLAC metadata block header: last-metadata-block flag (1 bit)
+	// and block type (7 bits), followed by a 24-bit block length. We don't
+	// need either value, as the STREAMINFO block always follows right here.
+	let block_header = rdr.read_u32::<BigEndian>()?;
+
+	let min_block_size = rdr.read_u16::<BigEndian>()?;
+	let max_block_size = rdr.read_u16::<BigEndian>()?;
+	let min_frame_size = rdr.read_uint::<BigEndian>(3)?;
+	let max_frame_size = rdr.read_uint::<BigEndian>(3)?;
+
+	// sample_rate (20 bits), channels - 1 (3 bits), bits_per_sample - 1 (5 bits)
+	// and total_samples (36 bits), packed into 64 bits big-endian.
+	let packed = rdr.read_u64::<BigEndian>()?;
+	let sample_rate = ((packed >> 44) & 0xf_ffff) as u32;
+	let channels = (((packed >> 41) & 0x7) + 1) as u8;
+	let bits_per_sample = (((packed >> 36) & 0x1f) + 1) as u8;
+	let total_samples = packed & 0xf_ffff_ffff;
+
+	let hdr :IdentHeader = IdentHeader {
+		channels : channels,
+		sample_rate : sample_rate,
+		bits_per_sample : bits_per_sample,
+		total_samples : total_samples,
+	};
+	Ok(hdr)
+}
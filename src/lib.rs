@@ -17,7 +17,8 @@ Supported formats:
 * Vorbis (Detect, Metadata)
 * Opus (Detect, Metadata)
 * Theora (Detect, Metadata)
-* Speex (Detect)
+* Speex (Detect, Metadata)
+* FLAC-in-Ogg (Detect, Metadata)
 
 Support will be extended in the future, especially for the Theora codec.
 */
@@ -40,14 +41,23 @@ macro_rules! try {
 mod vorbis;
 mod opus;
 mod theora;
+mod speex;
+mod flac;
+mod skeleton;
 
 use std::io;
+use std::io::{Cursor, Read};
 use ogg::{OggReadError, PacketReader};
 use std::time::Duration;
+use byteorder::{ReadBytesExt, LittleEndian};
 
 pub use vorbis::Metadata as VorbisMetadata;
 pub use opus::Metadata as OpusMetadata;
 pub use theora::Metadata as TheoraMetadata;
+pub use speex::Metadata as SpeexMetadata;
+pub use flac::Metadata as FlacMetadata;
+pub use skeleton::Info as SkeletonInfo;
+pub use skeleton::FisboneInfo;
 
 #[derive(Debug)]
 pub enum OggFormat {
@@ -59,10 +69,13 @@ pub enum OggFormat {
 	/// The Theora video format ([spec](https://www.theora.org/doc/Theora.pdf)).
 	Theora(TheoraMetadata),
 	/// The speex format ([spec](http://www.speex.org/docs/manual/speex-manual/)).
-	Speex,
+	Speex(SpeexMetadata),
+	/// FLAC encapsulated in an Ogg container
+	/// ([spec](https://xiph.org/flac/ogg_mapping.html)).
+	Flac(FlacMetadata),
 	/// The skeleton format with structure information
 	/// ([spec](https://wiki.xiph.org/Ogg_Skeleton_4))
-	Skeleton,
+	Skeleton(SkeletonInfo),
 	/// An format not supported by this crate or the magic code was corrupted.
 	Unknown,
 }
@@ -74,6 +87,7 @@ enum BareOggFormat {
 	Opus,
 	Theora,
 	Speex,
+	Flac,
 	Skeleton,
 }
 
@@ -88,6 +102,19 @@ pub enum OggMetadataError {
 	UnrecognizedFormat,
 	/// I/O error occured.
 	ReadError(std::io::Error),
+	/// A page's checksum didn't match its calculated value,
+	/// indicating the bitstream is corrupted or truncated
+	/// (e.g. from a download that didn't complete).
+	HashMismatch {
+		expected :u32,
+		calculated :u32,
+	},
+	/// The stream structure version of a page was not the one
+	/// this crate, and the underlying `ogg` crate, supports.
+	InvalidStreamStructVer(u8),
+	/// The data encountered was malformed and isn't a valid
+	/// Ogg bitstream, as opposed to merely an unsupported codec.
+	InvalidData,
 }
 
 impl std::error::Error for OggMetadataError {
@@ -96,6 +123,9 @@ impl std::error::Error for OggMetadataError {
 		match self {
 			&UnrecognizedFormat => "Unrecognized or invalid format",
 			&ReadError(_) => "I/O error",
+			&HashMismatch { .. } => "Page checksum mismatch, stream may be corrupted",
+			&InvalidStreamStructVer(_) => "Invalid stream structure version",
+			&InvalidData => "Invalid Ogg bitstream data",
 		}
 	}
 
@@ -124,6 +154,11 @@ impl From<OggReadError> for OggMetadataError {
 	fn from(err :OggReadError) -> OggMetadataError {
 		return match err {
 			OggReadError::ReadError(err) => OggMetadataError::ReadError(err),
+			OggReadError::HashMismatch(expected, calculated) =>
+				OggMetadataError::HashMismatch { expected : expected, calculated : calculated },
+			OggReadError::InvalidStreamStructVer(ver) =>
+				OggMetadataError::InvalidStreamStructVer(ver),
+			OggReadError::InvalidData => OggMetadataError::InvalidData,
 			_ => OggMetadataError::UnrecognizedFormat,
 		};
 	}
@@ -151,6 +186,53 @@ fn get_absgp_of_last_packet<'a, T :io::Read + io::Seek + 'a>(pck_rdr :&mut Packe
 	return Ok(pck.absgp_page);
 }
 
+/// Parses the body of a comment header (the part following the
+/// codec-specific magic bytes), as shared by the Vorbis and Opus
+/// comment header formats.
+///
+/// On any truncation or invalid UTF-8, this degrades to an empty
+/// vendor string and comment list instead of returning an error,
+/// as comments are considered non-essential metadata.
+fn parse_comment_list(data :&[u8]) -> (String, Vec<(String, String)>) {
+	// Reads a u32 LE length prefix followed by that many bytes, without
+	// trusting the prefix enough to eagerly allocate it: the length is
+	// untrusted file data, and a truncated/corrupted file (the very
+	// scenario this crate is built to tolerate) could otherwise claim
+	// a length near u32::MAX and force a multi-gigabyte allocation.
+	// Capping reads via `Read::take` means we only ever grow the buffer
+	// to the amount of data actually available.
+	fn read_length_prefixed<R :io::Read>(rdr :&mut R) -> Result<Vec<u8>, ()> {
+		let length = rdr.read_u32::<LittleEndian>().map_err(|_| ())? as u64;
+		let mut buf = Vec::new();
+		rdr.take(length).read_to_end(&mut buf).map_err(|_| ())?;
+		if buf.len() as u64 != length {
+			return Err(());
+		}
+		Ok(buf)
+	}
+	fn try_parse(data :&[u8]) -> Result<(String, Vec<(String, String)>), ()> {
+		let mut rdr = Cursor::new(data);
+		let vendor_buf = read_length_prefixed(&mut rdr)?;
+		let vendor = String::from_utf8(vendor_buf).map_err(|_| ())?;
+
+		let comment_count = rdr.read_u32::<LittleEndian>().map_err(|_| ())?;
+		let mut comments = Vec::new();
+		for _ in 0..comment_count {
+			let comment_buf = read_length_prefixed(&mut rdr)?;
+			let comment = String::from_utf8(comment_buf).map_err(|_| ())?;
+			match comment.find('=') {
+				Some(idx) => {
+					let (key, value) = comment.split_at(idx);
+					comments.push((key.to_uppercase(), value[1..].to_string()));
+				},
+				None => continue,
+			}
+		}
+		Ok((vendor, comments))
+	}
+	try_parse(data).unwrap_or_else(|_| (String::new(), Vec::new()))
+}
+
 fn identify_packet_data_by_magic(pck_data :&[u8]) -> Option<(usize, BareOggFormat)> {
 	// Magic sequences.
 	// https://www.xiph.org/vorbis/doc/Vorbis_I_spec.html#x1-620004.2.1
@@ -161,6 +243,8 @@ fn identify_packet_data_by_magic(pck_data :&[u8]) -> Option<(usize, BareOggForma
 	let theora_magic = &[0x80, 0x74, 0x68, 0x65, 0x6f, 0x72, 0x61];
 	// http://www.speex.org/docs/manual/speex-manual/node8.html
 	let speex_magic = &[0x53, 0x70, 0x65, 0x65, 0x78, 0x20, 0x20, 0x20];
+	// https://xiph.org/flac/ogg_mapping.html
+	let flac_magic = &[0x7f, 0x46, 0x4c, 0x41, 0x43];
 	// https://wiki.xiph.org/Ogg_Skeleton_4#Ogg_Skeleton_version_4.0_Format_Specification
 	let skeleton_magic = &[0x66, 105, 115, 104, 101, 97, 100, 0];
 
@@ -174,6 +258,7 @@ fn identify_packet_data_by_magic(pck_data :&[u8]) -> Option<(usize, BareOggForma
 		0x4f if pck_data.starts_with(opus_magic) => (opus_magic.len(), Opus),
 		0x80 if pck_data.starts_with(theora_magic) => (theora_magic.len(), Theora),
 		0x53 if pck_data.starts_with(speex_magic) => (speex_magic.len(), Speex),
+		0x7f if pck_data.starts_with(flac_magic) => (flac_magic.len(), Flac),
 		0x66 if pck_data.starts_with(skeleton_magic) => (speex_magic.len(), Skeleton),
 
 		_ => return None,
@@ -188,41 +273,83 @@ fn needs_last_packet_absgp(bare_format :BareOggFormat) -> bool {
 	match bare_format {
 		BareOggFormat::Vorbis => true,
 		BareOggFormat::Opus => true,
-		BareOggFormat::Theora => false,
-		BareOggFormat::Speex => false,
+		BareOggFormat::Theora => true,
+		BareOggFormat::Speex => true,
+		BareOggFormat::Flac => false,
 		BareOggFormat::Skeleton => false,
 	}
 }
 
-fn parse_format(pck_data :&[u8], bare_format :BareOggFormat,
+fn parse_format(pck_data :&[u8], comment_pck_data :Option<&[u8]>, bare_format :BareOggFormat,
 		last_packet_absgp :Option<u64>) -> Result<OggFormat, OggMetadataError> {
 	use OggFormat::*;
 	Ok(match bare_format {
 		BareOggFormat::Vorbis => {
 			let ident_hdr = try!(vorbis::read_header_ident(pck_data));
+			let (vendor, comments) = match comment_pck_data {
+				Some(d) => vorbis::read_header_comment(d),
+				None => (String::new(), Vec::new()),
+			};
 			Vorbis(VorbisMetadata {
 				channels : ident_hdr.channels,
 				sample_rate : ident_hdr.sample_rate,
 				length_in_samples : last_packet_absgp,
+				vendor : vendor,
+				comments : comments,
 			})
 		},
 		BareOggFormat::Opus => {
 			let ident_hdr = try!(opus::read_header_ident(pck_data));
+			let (vendor, comments) = match comment_pck_data {
+				Some(d) => opus::read_header_comment(d),
+				None => (String::new(), Vec::new()),
+			};
 			Opus(OpusMetadata {
 				output_channels : ident_hdr.output_channels,
 				length_in_48khz_samples : last_packet_absgp.map(
 					|l| l - (ident_hdr.pre_skip as u64)),
+				vendor : vendor,
+				comments : comments,
+				input_sample_rate : ident_hdr.input_sample_rate,
+				output_gain_db : (ident_hdr.output_gain as f32) / 256.0,
+				mapping_family : ident_hdr.channel_mapping_family,
+				stream_count : ident_hdr.stream_count,
+				coupled_count : ident_hdr.coupled_count,
 			})
 		},
 		BareOggFormat::Theora => {
 			let ident_hdr = try!(theora::read_header_ident(pck_data));
+			let length_in_frames = last_packet_absgp.map(|absgp| {
+				let shift = ident_hdr.keyframe_granule_shift;
+				let low_bits_mask = (1u64 << shift) - 1;
+				(absgp >> shift) + (absgp & low_bits_mask)
+			});
 			Theora(TheoraMetadata {
 				pixels_width : ident_hdr.picture_region_width,
 				pixels_height : ident_hdr.picture_region_height,
+				framerate : (ident_hdr.fps_numerator, ident_hdr.fps_denominator),
+				aspect_ratio : (ident_hdr.aspect_numerator, ident_hdr.aspect_denominator),
+				length_in_frames : length_in_frames,
 			})
 		},
-		BareOggFormat::Speex => Speex,
-		BareOggFormat::Skeleton => Skeleton,
+		BareOggFormat::Speex => {
+			let ident_hdr = try!(speex::read_header_ident(pck_data));
+			Speex(SpeexMetadata {
+				channels : ident_hdr.channels,
+				sample_rate : ident_hdr.sample_rate,
+				length_in_samples : last_packet_absgp,
+			})
+		},
+		BareOggFormat::Flac => {
+			let ident_hdr = try!(flac::read_header_ident(pck_data));
+			Flac(FlacMetadata {
+				channels : ident_hdr.channels,
+				sample_rate : ident_hdr.sample_rate,
+				bits_per_sample : ident_hdr.bits_per_sample,
+				length_in_samples : Some(ident_hdr.total_samples),
+			})
+		},
+		BareOggFormat::Skeleton => Skeleton(SkeletonInfo { streams : Vec::new() }),
 	})
 }
 
@@ -253,6 +380,19 @@ pub fn read_format<'a, T :io::Read + io::Seek + 'a>(rdr :T)
 
 	let mut res = Vec::new();
 
+	let needs_comment_header = match id_inner.1 {
+		BareOggFormat::Vorbis | BareOggFormat::Opus => true,
+		_ => false,
+	};
+
+	// The comment header is non-essential metadata, so if we fail to
+	// read it we don't bail out of reading the file altogether.
+	let comment_pck = if needs_comment_header {
+		pck_rdr.read_packet_expected().ok()
+	} else {
+		None
+	};
+
 	let simple_seek_to_end_is_needed = needs_last_packet_absgp(id_inner.1);
 
 	let last_packet_absgp = if simple_seek_to_end_is_needed {
@@ -262,6 +402,7 @@ pub fn read_format<'a, T :io::Read + io::Seek + 'a>(rdr :T)
 	};
 
 	res.push(try!(parse_format(&pck.data[id_inner.0..],
+		comment_pck.as_ref().map(|p| p.data.as_slice()),
 		id_inner.1, last_packet_absgp)));
 
 	if id_inner.1 == BareOggFormat::Skeleton {
@@ -270,25 +411,26 @@ pub fn read_format<'a, T :io::Read + io::Seek + 'a>(rdr :T)
 		// Loop until the skeleton stream ended
 		// and record any opening streams.
 		let mut streams = HashMap::new();
+		let mut fisbones = Vec::new();
 		loop {
 			let pck_cur = try!(pck_rdr.read_packet_expected());
 
 			if pck_cur.stream_serial == pck.stream_serial {
-				/*
 				// "fisbone\0"
 				let fisbone_magic = [0x66, 0x69, 0x73, 0x62, 0x6f, 0x6e, 0x65, 0x00];
 				// "index\0"
 				let index_magic = [0x69, 0x6e, 0x64, 0x65, 0x78, 0x00];
 				match () {
 					() if pck_cur.data.starts_with(&fisbone_magic) => {
-						println!("==> bone!");
-					},
-					() if pck_cur.data.starts_with(&index_magic) => {
-						println!("==> index!");
+						// A malformed fisbone packet is not fatal to reading
+						// the rest of the file, so we just skip recording it.
+						if let Ok(info) = skeleton::read_fisbone(&pck_cur.data) {
+							fisbones.push(info);
+						}
 					},
+					() if pck_cur.data.starts_with(&index_magic) => {},
 					_ => {},
 				}
-				*/
 				if pck_cur.last_packet {
 					break;
 				}
@@ -350,7 +492,7 @@ pub fn read_format<'a, T :io::Read + io::Seek + 'a>(rdr :T)
 					try!(Err(OggMetadataError::UnrecognizedFormat));
 				}
 				let st = try!(parse_format(&(stream.1).data[(stream.0).0..],
-					(stream.0).1, Some(pck_cur.absgp_page)));
+					None, (stream.0).1, Some(pck_cur.absgp_page)));
 				res.push(st);
 			}
 			break;
@@ -359,9 +501,13 @@ pub fn read_format<'a, T :io::Read + io::Seek + 'a>(rdr :T)
 		// Add all streams we couldn't find a last packet for.
 		for (_,stream) in streams.iter() {
 			let st = try!(parse_format(&(stream.1).data[(stream.0).0..],
-				(stream.0).1, None));
+				None, (stream.0).1, None));
 			res.push(st);
 		}
+
+		// res[0] is the placeholder pushed for the skeleton stream
+		// itself, before we had parsed its fisbone packets.
+		res[0] = OggFormat::Skeleton(SkeletonInfo { streams : fisbones });
 	}
 
 	return Ok(res);
@@ -24,6 +24,33 @@ pub struct Metadata {
 	/// the per-page sample counter operates on
 	/// units of 48khz.
 	pub length_in_48khz_samples :Option<u64>,
+	/// The vendor string from the comment header.
+	pub vendor :String,
+	/// The tags from the comment header, as `(key, value)` pairs.
+	///
+	/// Keys are uppercased per the Vorbis comment spec that Opus reuses,
+	/// e.g. `ARTIST`, `TITLE`, `ALBUM`.
+	pub comments :Vec<(String, String)>,
+	/// The sample rate of the original input, before being resampled
+	/// to Opus' internal 48kHz, for informational purposes only.
+	pub input_sample_rate :u32,
+	/// The gain to apply to the decoded output, in dB.
+	pub output_gain_db :f32,
+	/// The channel mapping family, as defined by RFC 7845 section 5.1.1.
+	///
+	/// Family 0 covers mono/stereo, family 1 covers the Vorbis channel
+	/// order for up to 8 channels, and 255 means an application-defined
+	/// mapping. Any other value means an unrecognized multistream layout.
+	pub mapping_family :u8,
+	/// The number of Opus streams making up the multistream layout.
+	///
+	/// Only present if `mapping_family != 0`.
+	pub stream_count :Option<u8>,
+	/// The number of streams in the multistream layout carrying two
+	/// coupled channels.
+	///
+	/// Only present if `mapping_family != 0`.
+	pub coupled_count :Option<u8>,
 }
 
 impl AudioMetadata for Metadata {
@@ -55,6 +82,11 @@ impl fmt::Debug for Metadata {
 pub struct IdentHeader {
 	pub output_channels :u8,
 	pub pre_skip :u16,
+	pub input_sample_rate :u32,
+	pub output_gain :i16,
+	pub channel_mapping_family :u8,
+	pub stream_count :Option<u8>,
+	pub coupled_count :Option<u8>,
 }
 
 pub fn read_header_ident(packet :&[u8]) -> Result<IdentHeader, OggMetadataError> {
@@ -68,11 +100,47 @@ pub fn read_header_ident(packet :&[u8]) -> Result<IdentHeader, OggMetadataError>
 	}
 	let output_channels = try!(rdr.read_u8());
 	let pre_skip = try!(rdr.read_u16::<LittleEndian>());
+	let input_sample_rate = try!(rdr.read_u32::<LittleEndian>());
+	let output_gain = try!(rdr.read_i16::<LittleEndian>());
+	let channel_mapping_family = try!(rdr.read_u8());
+
+	// A non-zero mapping family means this is a multistream layout:
+	// the stream/coupled counts and a per-output-channel mapping
+	// table follow.
+	let (stream_count, coupled_count) = if channel_mapping_family != 0 {
+		let stream_count = try!(rdr.read_u8());
+		let coupled_count = try!(rdr.read_u8());
+		for _ in 0..output_channels {
+			try!(rdr.read_u8());
+		}
+		(Some(stream_count), Some(coupled_count))
+	} else {
+		(None, None)
+	};
 
 	let hdr :IdentHeader = IdentHeader {
 		output_channels : output_channels,
 		pre_skip : pre_skip,
+		input_sample_rate : input_sample_rate,
+		output_gain : output_gain,
+		channel_mapping_family : channel_mapping_family,
+		stream_count : stream_count,
+		coupled_count : coupled_count,
 	};
 	return Ok(hdr);
 }
 
+/// Parses the "OpusTags" comment header packet, returning the vendor
+/// string and the tag list.
+///
+/// If the packet doesn't carry the comment header magic, or the
+/// contents are truncated or not valid UTF-8, this degrades to an
+/// empty vendor string and comment list rather than erroring.
+pub fn read_header_comment(packet :&[u8]) -> (String, Vec<(String, String)>) {
+	let magic = b"OpusTags";
+	if !packet.starts_with(magic) {
+		return (String::new(), Vec::new());
+	}
+	crate::parse_comment_list(&packet[magic.len()..])
+}
+
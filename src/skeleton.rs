@@ -0,0 +1,101 @@
+// Ogg metadata reader written in Rust
+//
+// Copyright (c) 2016 est31 <MTest31@outlook.com>
+// and contributors. All rights reserved.
+// Licensed under MIT license, or Apache 2 license,
+// at your option. Please see the LICENSE file
+// attached to this source distribution for details.
+
+use std::io::Cursor;
+use byteorder::{ReadBytesExt, LittleEndian};
+use crate::OggMetadataError;
+
+/**
+Structure information gathered from the Skeleton logical stream.
+*/
+#[derive(Debug)]
+pub struct Info {
+	/// One entry per fisbone packet found, i.e. per content
+	/// stream the skeleton describes.
+	pub streams :Vec<FisboneInfo>,
+}
+
+/// The contents of a single `fisbone` packet, describing the timing
+/// and message headers of one content stream.
+#[derive(Debug)]
+pub struct FisboneInfo {
+	/// The serial number of the content stream this fisbone describes.
+	pub serial_number :u32,
+	pub num_header_packets :u32,
+	pub granule_rate_numerator :u64,
+	pub granule_rate_denominator :u64,
+	pub base_granule :u64,
+	pub preroll :u32,
+	pub granule_shift :u8,
+	/// Message headers such as `Content-Type` or `Role`, as
+	/// `(key, value)` pairs, in the order they appear in the packet.
+	pub message_headers :Vec<(String, String)>,
+}
+
+/// Parses a `fisbone` packet.
+///
+/// Returns `Err` if the packet doesn't carry the fisbone magic, or
+/// if the fixed-size fields are truncated. A message header offset
+/// pointing past the end of the packet, or a message header block
+/// that is not valid UTF-8, gives an empty header list.
+pub fn read_fisbone(packet :&[u8]) -> Result<FisboneInfo, OggMetadataError> {
+	let magic = b"fisbone\0";
+	if !packet.starts_with(magic) {
+		return Err(OggMetadataError::UnrecognizedFormat);
+	}
+
+	let mut rdr = Cursor::new(&packet[magic.len()..]);
+	let message_header_offset = rdr.read_u32::<LittleEndian>()?;
+	let serial_number = rdr.read_u32::<LittleEndian>()?;
+	let num_header_packets = rdr.read_u32::<LittleEndian>()?;
+	let granule_rate_numerator = rdr.read_u64::<LittleEndian>()?;
+	let granule_rate_denominator = rdr.read_u64::<LittleEndian>()?;
+	let base_granule = rdr.read_u64::<LittleEndian>()?;
+	let preroll = rdr.read_u32::<LittleEndian>()?;
+	let granule_shift = rdr.read_u8()?;
+	// 3 padding bytes
+	rdr.read_u8()?;
+	rdr.read_u8()?;
+	rdr.read_u8()?;
+
+	// The message header offset counts from the offset field itself,
+	// i.e. from right after the magic.
+	let message_headers = match packet[magic.len()..].get(message_header_offset as usize..) {
+		Some(data) => parse_message_headers(data),
+		None => Vec::new(),
+	};
+
+	Ok(FisboneInfo {
+		serial_number : serial_number,
+		num_header_packets : num_header_packets,
+		granule_rate_numerator : granule_rate_numerator,
+		granule_rate_denominator : granule_rate_denominator,
+		base_granule : base_granule,
+		preroll : preroll,
+		granule_shift : granule_shift,
+		message_headers : message_headers,
+	})
+}
+
+/// Parses the `KEY: VALUE\r\n` message header block following the
+/// fixed-size part of a fisbone packet. Degrades to an empty list
+/// on invalid UTF-8 rather than erroring; lines without a `": "`
+/// separator are skipped.
+fn parse_message_headers(data :&[u8]) -> Vec<(String, String)> {
+	let text = match ::std::str::from_utf8(data) {
+		Ok(t) => t,
+		Err(_) => return Vec::new(),
+	};
+	text.split("\r\n")
+		.filter_map(|line| {
+			line.find(": ").map(|idx| {
+				(line[..idx].to_string(), line[idx + 2..].to_string())
+			})
+		})
+		.collect()
+}
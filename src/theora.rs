@@ -9,6 +9,7 @@
 use std::io::Cursor;
 use byteorder::{ReadBytesExt, BigEndian};
 use std::fmt;
+use std::time::Duration;
 use crate::OggMetadataError;
 
 /**
@@ -17,17 +18,53 @@ Metadata for the Theora video codec.
 pub struct Metadata {
 	pub pixels_width :u32,
 	pub pixels_height :u32,
+	pub framerate :(u32, u32),
+	pub aspect_ratio :(u32, u32),
+	pub length_in_frames :Option<u64>,
+}
+
+impl Metadata {
+	/// Returns the duration of the video, computed from the frame
+	/// count and the frame rate, if the frame count is known.
+	pub fn get_duration(&self) -> Option<Duration> {
+		self.length_in_frames.map(|frames| {
+			let (fps_numerator, fps_denominator) = self.framerate;
+			let duration_raw_secs = (frames as f64) * (fps_denominator as f64)
+				/ (fps_numerator as f64);
+			Duration::from_millis((duration_raw_secs * 1000.0) as u64)
+		})
+	}
 }
 
 impl fmt::Debug for Metadata {
 	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-		write!(f, "size {}x{}", self.pixels_width, self.pixels_height)
+		match self.length_in_frames {
+			Some(frames) => {
+				let (fps_numerator, fps_denominator) = self.framerate;
+				let duration_raw_secs = (frames as f64) * (fps_denominator as f64)
+					/ (fps_numerator as f64);
+				write!(f, "size {}x{}, with {}/{} fps and duration of {}",
+					self.pixels_width, self.pixels_height,
+					fps_numerator, fps_denominator,
+					crate::format_duration(duration_raw_secs))
+			},
+			None => write!(f, "size {}x{}, with {}/{} fps",
+				self.pixels_width, self.pixels_height,
+				self.framerate.0, self.framerate.1),
+		}
 	}
 }
 
 pub struct IdentHeader {
 	pub picture_region_width :u32,
 	pub picture_region_height :u32,
+	pub fps_numerator :u32,
+	pub fps_denominator :u32,
+	pub aspect_numerator :u32,
+	pub aspect_denominator :u32,
+	pub colorspace :u8,
+	pub pixel_format :u8,
+	pub keyframe_granule_shift :u8,
 }
 
 #[allow(unused_variables)]
@@ -47,10 +84,39 @@ pub fn read_header_ident(packet :&[u8]) -> Result<IdentHeader, OggMetadataError>
 	// Height of the picture region in pixels
 	let pich = rdr.read_uint::<BigEndian>(3)? as u32;
 
+	// X/Y offset of the picture region within the frame, in pixels
+	let picx = rdr.read_u8()?;
+	let picy = rdr.read_u8()?;
+
+	// Frame rate, as a fraction of numerator over denominator
+	let fps_numerator = rdr.read_u32::<BigEndian>()?;
+	let fps_denominator = rdr.read_u32::<BigEndian>()?;
+
+	// Pixel aspect ratio, as a fraction of numerator over denominator
+	let aspect_numerator = rdr.read_uint::<BigEndian>(3)? as u32;
+	let aspect_denominator = rdr.read_uint::<BigEndian>(3)? as u32;
+
+	let colorspace = rdr.read_u8()?;
+	// Nominal bitrate, unused by this crate.
+	let nominal_bitrate = rdr.read_uint::<BigEndian>(3)?;
+
+	// The remaining 16 bits pack, from most to least significant bit,
+	// the quality hint (6 bits), the keyframe granule shift (5 bits),
+	// the pixel format (2 bits) and 3 reserved bits.
+	let packed = rdr.read_u16::<BigEndian>()?;
+	let keyframe_granule_shift = ((packed >> 5) & 0x1f) as u8;
+	let pixel_format = ((packed >> 3) & 0x3) as u8;
+
 	let hdr :IdentHeader = IdentHeader {
 		picture_region_width : picw,
 		picture_region_height : pich,
+		fps_numerator : fps_numerator,
+		fps_denominator : fps_denominator,
+		aspect_numerator : aspect_numerator,
+		aspect_denominator : aspect_denominator,
+		colorspace : colorspace,
+		pixel_format : pixel_format,
+		keyframe_granule_shift : keyframe_granule_shift,
 	};
 	Ok(hdr)
 }
-
@@ -20,6 +20,12 @@ pub struct Metadata {
 	pub channels :u8,
 	pub sample_rate :u32,
 	pub length_in_samples :Option<u64>,
+	/// The vendor string from the comment header.
+	pub vendor :String,
+	/// The tags from the comment header, as `(key, value)` pairs.
+	///
+	/// Keys are uppercased per the Vorbis comment spec, e.g. `ARTIST`, `TITLE`, `ALBUM`.
+	pub comments :Vec<(String, String)>,
 }
 
 impl AudioMetadata for Metadata {
@@ -68,3 +74,18 @@ pub fn read_header_ident(packet :&[u8]) -> Result<IdentHeader, OggMetadataError>
 	};
 	Ok(hdr)
 }
+
+/// Parses the Vorbis comment header packet, returning the vendor
+/// string and the tag list.
+///
+/// If the packet doesn't carry the comment header magic, or the
+/// contents are truncated or not valid UTF-8, this degrades to an
+/// empty vendor string and comment list rather than erroring.
+pub fn read_header_comment(packet :&[u8]) -> (String, Vec<(String, String)>) {
+	// byte 0x03 + "vorbis"
+	let magic = &[0x03, 0x76, 0x6f, 0x72, 0x62, 0x69, 0x73];
+	if !packet.starts_with(magic) {
+		return (String::new(), Vec::new());
+	}
+	crate::parse_comment_list(&packet[magic.len()..])
+}